@@ -1,11 +1,46 @@
-use std::{fs::File, io::Write};
-use hound;
+use std::{fs::File, io::{BufWriter, Read, Write}};
+use hound::{SampleFormat, WavReader};
 
 fn show_info() {
     eprintln!("MUSI-6106 Assignment Executable");
     eprintln!("(c) 2024 Stephen Garrett & Ian Clester");
 }
 
+/// Reads all samples from `reader` into one f32 buffer per channel.
+/// Integer samples are scaled to [-1, 1); float samples are passed through.
+fn read_planar<R: Read>(mut reader: WavReader<R>) -> Vec<Vec<f32>> {
+    let spec = reader.spec();
+    let num_channels = spec.channels as usize;
+
+    // Deinterleave while converting, so no interleaved f32 copy is made
+    let mut channels: Vec<Vec<f32>> = vec![Vec::new(); num_channels];
+    match (spec.sample_format, spec.bits_per_sample) {
+        (SampleFormat::Int, 8) => {
+            for (i, sample) in reader.samples::<i8>().enumerate() {
+                channels[i % num_channels].push(sample.unwrap() as f32 / 128.0);
+            }
+        }
+        (SampleFormat::Int, 16) => {
+            for (i, sample) in reader.samples::<i16>().enumerate() {
+                channels[i % num_channels].push(sample.unwrap() as f32 / 32768.0);
+            }
+        }
+        (SampleFormat::Int, 24) => {
+            for (i, sample) in reader.samples::<i32>().enumerate() {
+                channels[i % num_channels].push(sample.unwrap() as f32 / 8388608.0);
+            }
+        }
+        (SampleFormat::Float, 32) => {
+            for (i, sample) in reader.samples::<f32>().enumerate() {
+                channels[i % num_channels].push(sample.unwrap());
+            }
+        }
+        (format, bits) => panic!("unsupported sample format: {}-bit {:?}", bits, format),
+    }
+
+    channels
+}
+
 fn main() {
    show_info();
 
@@ -20,26 +55,95 @@ fn main() {
 
     // Open the input wave file and determine number of channels
     // TODO: your code here; see `hound::WavReader::open`.
-    let mut reader = hound::WavReader::open(signal_path).unwrap();
+    let reader = hound::WavReader::open(signal_path).unwrap();
 
     // Read audio data and write it to the output text file (one column per channel)
     // TODO: your code here; we suggest using `hound::WavReader::samples`, `File::create`, and `write!`.
     // Remember to convert the samples to floating point values and respect the number of channels!
-    let mut left_channel: Vec<f32> = Vec::new();
-    let mut right_channel: Vec<f32> = Vec::new();
-    for sample in reader.samples::<i16>() {
-        let sample_value = sample.unwrap();
+    let channels = read_planar(reader);
 
-        if left_channel.len() <= right_channel.len() {
-            left_channel.push(sample_value as f32 / 32768.0);
-        } else {
-            right_channel.push(sample_value as f32 / 32768.0);
-        }        
+    let mut file = BufWriter::new(File::create(output_path).unwrap());
+    for n in 0..channels[0].len() {
+        for (c, channel) in channels.iter().enumerate() {
+            if c > 0 {
+                write!(file, ", ").unwrap();
+            }
+            write!(file, "{}", channel[n]).unwrap();
+        }
+        writeln!(file).unwrap();
     }
+    file.flush().unwrap();
+
+}
 
-    let mut file = File::create(output_path).unwrap();
-    for (l, r) in left_channel.iter().zip(right_channel.iter()) {
-        write!(file, "{}, {}\n", l, r).unwrap();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hound::{WavSpec, WavWriter};
+    use std::io::Cursor;
+
+    fn spec(channels: u16, bits_per_sample: u16, sample_format: SampleFormat) -> WavSpec {
+        WavSpec { channels, sample_rate: 44100, bits_per_sample, sample_format }
+    }
+
+    fn encode_int(spec: WavSpec, samples: &[i32]) -> Vec<u8> {
+        let mut cursor = Cursor::new(Vec::new());
+        let mut writer = WavWriter::new(&mut cursor, spec).unwrap();
+        for &sample in samples {
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+        cursor.into_inner()
     }
 
+    fn decode(bytes: Vec<u8>) -> Vec<Vec<f32>> {
+        read_planar(WavReader::new(Cursor::new(bytes)).unwrap())
+    }
+
+    #[test]
+    fn test_read_8bit_stereo() {
+        let bytes = encode_int(spec(2, 8, SampleFormat::Int), &[0, 1, -128, 64]);
+        assert_eq!(decode(bytes), vec![vec![0.0, -1.0], vec![1.0 / 128.0, 0.5]]);
+    }
+
+    #[test]
+    fn test_read_16bit_mono() {
+        let bytes = encode_int(spec(1, 16, SampleFormat::Int), &[0, 16384, -32768]);
+        assert_eq!(decode(bytes), vec![vec![0.0, 0.5, -1.0]]);
+    }
+
+    #[test]
+    fn test_read_16bit_stereo() {
+        let bytes = encode_int(spec(2, 16, SampleFormat::Int), &[16384, -16384, 0, 32767]);
+        assert_eq!(decode(bytes), vec![vec![0.5, 0.0], vec![-0.5, 32767.0 / 32768.0]]);
+    }
+
+    #[test]
+    fn test_read_24bit_mono() {
+        let bytes = encode_int(spec(1, 24, SampleFormat::Int), &[0, 4194304, -8388608]);
+        assert_eq!(decode(bytes), vec![vec![0.0, 0.5, -1.0]]);
+    }
+
+    #[test]
+    fn test_read_24bit_stereo() {
+        let bytes = encode_int(spec(2, 24, SampleFormat::Int), &[-4194304, 2097152, 8388607, 0]);
+        assert_eq!(decode(bytes), vec![vec![-0.5, 8388607.0 / 8388608.0], vec![0.25, 0.0]]);
+    }
+
+    #[test]
+    fn test_read_float_stereo() {
+        let mut cursor = Cursor::new(Vec::new());
+        let mut writer = WavWriter::new(&mut cursor, spec(2, 32, SampleFormat::Float)).unwrap();
+        for sample in [0.25f32, -0.75, 1.0, 0.0] {
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+        assert_eq!(decode(cursor.into_inner()), vec![vec![0.25, 1.0], vec![-0.75, 0.0]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "unsupported sample format")]
+    fn test_read_unsupported_format() {
+        decode(encode_int(spec(1, 32, SampleFormat::Int), &[0]));
+    }
 }